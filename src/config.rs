@@ -5,13 +5,51 @@ use serde::{Deserialize, Serialize};
 
 pub const APP_NAME: &str = "gh-token-switch";
 
+/// Hostname used for aliases with no entry in `Config::hosts`.
+pub const DEFAULT_HOST: &str = "github.com";
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
     pub aliases: Vec<String>,
     pub fingerprints: HashMap<String, String>,
     pub notifications: NotificationConfig,
+    pub credential: CredentialConfig,
+    /// Per-alias `gh` hostname, for GitHub Enterprise Server profiles.
+    /// Aliases with no entry here target `DEFAULT_HOST`.
+    pub hosts: HashMap<String, String>,
+    /// Matchers that auto-select an alias for `use` based on the current
+    /// directory or git remote, checked in order.
+    pub rules: Vec<Rule>,
     pub last_used_alias: Option<String>,
+    pub last_notification: Option<LastNotification>,
+}
+
+/// A directory/remote matcher that auto-selects `alias` for `use` with no
+/// argument, e.g. `match = "~/work/**"` or `match = "*/acme-org/*"`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    #[serde(rename = "match")]
+    pub matcher: String,
+    pub alias: String,
+}
+
+/// Returns the `gh` hostname configured for `alias`, falling back to
+/// `DEFAULT_HOST` when none is recorded.
+pub fn host_for<'a>(cfg: &'a Config, alias: &str) -> &'a str {
+    cfg.hosts
+        .get(alias)
+        .map(String::as_str)
+        .unwrap_or(DEFAULT_HOST)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CredentialConfig {
+    /// External credential-process helper to use instead of the OS
+    /// keyring, e.g. `process = "gh-token-switch-1password"`. The helper
+    /// is invoked as `<process> get|store|erase <alias>`.
+    pub process: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +58,11 @@ pub struct NotificationConfig {
     pub enabled: bool,
     pub only_when_no_tty: bool,
     pub only_on_implicit_cycle: bool,
+    pub email: Option<EmailNotificationConfig>,
+    pub webhook: Option<WebhookNotificationConfig>,
+    /// Suppress a repeated identical notification (same event + alias)
+    /// fired within this many seconds of the last one.
+    pub dedupe_seconds: u64,
 }
 
 impl Default for NotificationConfig {
@@ -28,10 +71,61 @@ impl Default for NotificationConfig {
             enabled: true,
             only_when_no_tty: true,
             only_on_implicit_cycle: true,
+            email: None,
+            webhook: None,
+            dedupe_seconds: 5,
         }
     }
 }
 
+/// Records the last notification that was dispatched (not suppressed),
+/// whether or not its channels succeeded, so a repeat within
+/// `dedupe_seconds` can be suppressed across invocations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastNotification {
+    pub event: String,
+    pub alias: String,
+    pub unix_timestamp: u64,
+}
+
+/// SMTP delivery, configured under `[notifications.email]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailNotificationConfig {
+    pub from: String,
+    pub to: String,
+    pub smtp_host: String,
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    pub username: String,
+    pub password: String,
+    #[serde(default)]
+    pub encryption: SmtpEncryption,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+/// How to secure the SMTP connection. `StartTls` is the right choice for
+/// the common submission port 587 (plaintext greeting, then upgrade);
+/// `Wrapper` is implicit TLS from the first byte, used on port 465.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpEncryption {
+    None,
+    #[default]
+    StartTls,
+    Wrapper,
+}
+
+/// Webhook delivery, configured under `[notifications.webhook]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookNotificationConfig {
+    pub url: String,
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
 pub fn load_config() -> Result<Config> {
     let path = config_path()?;
     if !path.exists() {
@@ -107,7 +201,11 @@ work = "abc123"
             .into_iter()
             .collect(),
             notifications: Default::default(),
+            credential: Default::default(),
+            hosts: Default::default(),
+            rules: Default::default(),
             last_used_alias: Some("personal".into()),
+            last_notification: None,
         };
 
         let serialized = toml::to_string(&original).expect("serialize config");
@@ -0,0 +1,185 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, anyhow, bail};
+use keyring::Entry;
+
+use crate::config::Config;
+
+const SERVICE: &str = "github-cli-login-switcher";
+
+/// Abstracts over where a profile's token actually lives, so command
+/// handlers don't need to know whether they're talking to the OS keyring
+/// or an external credential-process helper.
+pub trait CredentialStore {
+    fn get(&self, alias: &str) -> Result<String>;
+    fn store(&self, alias: &str, token: &str) -> Result<()>;
+    /// Erase a credential, as used by `delete`, where the caller just wants
+    /// the alias gone. The keyring backend ignores a missing credential; the
+    /// external helper backend returns an error whenever the helper exits
+    /// non-zero, including for an alias with nothing stored.
+    fn erase(&self, alias: &str) -> Result<()>;
+    /// Erase a credential, surfacing any failure instead of swallowing it.
+    /// Use this where losing the old credential without noticing would be
+    /// a problem, e.g. `rename`, which has already written the new one.
+    fn erase_strict(&self, alias: &str) -> Result<()> {
+        self.erase(alias)
+    }
+}
+
+/// Returns the configured backend: an external process helper when
+/// `[credential].process` is set, otherwise the platform keyring.
+pub fn credential_store(cfg: &Config) -> Box<dyn CredentialStore> {
+    match cfg.credential.process.as_deref() {
+        Some(command) => Box::new(ExternalProcessStore::new(command.to_string())),
+        None => Box::new(KeyringStore),
+    }
+}
+
+pub struct KeyringStore;
+
+impl CredentialStore for KeyringStore {
+    fn get(&self, alias: &str) -> Result<String> {
+        entry(alias)?
+            .get_password()
+            .with_context(|| format!("no token found for alias '{alias}'"))
+    }
+
+    fn store(&self, alias: &str, token: &str) -> Result<()> {
+        entry(alias)?
+            .set_password(token)
+            .with_context(|| format!("failed storing token for alias '{alias}'"))
+    }
+
+    fn erase(&self, alias: &str) -> Result<()> {
+        let _ = entry(alias)?.delete_credential();
+        Ok(())
+    }
+
+    fn erase_strict(&self, alias: &str) -> Result<()> {
+        entry(alias)?
+            .delete_credential()
+            .with_context(|| format!("failed deleting credential for alias '{alias}'"))
+    }
+}
+
+fn entry(alias: &str) -> Result<Entry> {
+    Entry::new(SERVICE, alias).context("failed to create keyring entry")
+}
+
+/// Shells out to an external credential-process helper, modeled on
+/// Cargo's credential-process (RFC 2730): `<cmd> get|store|erase <alias>`.
+pub struct ExternalProcessStore {
+    command: String,
+}
+
+impl ExternalProcessStore {
+    pub fn new(command: String) -> Self {
+        Self { command }
+    }
+
+    fn run(&self, action: &str, alias: &str, stdin_payload: Option<&str>) -> Result<Vec<u8>> {
+        let mut child = Command::new(&self.command)
+            .args([action, alias])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .with_context(|| format!("failed to run credential helper '{}'", self.command))?;
+
+        if let Some(payload) = stdin_payload {
+            let stdin = child
+                .stdin
+                .as_mut()
+                .ok_or_else(|| anyhow!("failed to open stdin for credential helper"))?;
+            stdin
+                .write_all(payload.as_bytes())
+                .context("failed to write to credential helper stdin")?;
+        } else {
+            // Drop stdin so helpers that read to EOF don't hang on `get`/`erase`.
+            drop(child.stdin.take());
+        }
+
+        let output = child
+            .wait_with_output()
+            .with_context(|| format!("failed waiting for credential helper '{}'", self.command))?;
+
+        if !output.status.success() {
+            bail!(
+                "credential helper '{}' exited with {} during '{action}' for alias '{alias}'",
+                self.command,
+                output.status
+            );
+        }
+
+        Ok(output.stdout)
+    }
+}
+
+impl CredentialStore for ExternalProcessStore {
+    fn get(&self, alias: &str) -> Result<String> {
+        let stdout = self.run("get", alias, None)?;
+        Ok(String::from_utf8(stdout)
+            .context("credential helper output was not utf-8")?
+            .trim()
+            .to_string())
+    }
+
+    fn store(&self, alias: &str, token: &str) -> Result<()> {
+        let payload = build_store_payload(token)?;
+        self.run("store", alias, Some(&payload))?;
+        Ok(())
+    }
+
+    fn erase(&self, alias: &str) -> Result<()> {
+        self.run("erase", alias, None)?;
+        Ok(())
+    }
+}
+
+/// Builds the `{"token":"..."}` payload written to a `store` helper's
+/// stdin, via a real JSON encoder so control characters in the token
+/// (newlines, etc.) round-trip correctly.
+fn build_store_payload(token: &str) -> Result<String> {
+    serde_json::to_string(&serde_json::json!({ "token": token }))
+        .context("failed to serialize credential payload")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, process};
+
+    use super::{CredentialStore, ExternalProcessStore, build_store_payload};
+
+    #[test]
+    fn build_store_payload_round_trips_control_characters() {
+        let token = "line one\nline \"two\"\\ three";
+
+        let payload = build_store_payload(token).expect("payload should serialize");
+        let parsed: serde_json::Value =
+            serde_json::from_str(&payload).expect("payload should be valid JSON");
+
+        assert_eq!(parsed["token"], token);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn external_process_store_trims_whitespace_from_get_stdout() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script_path =
+            std::env::temp_dir().join(format!("gh-token-switch-test-get-{}.sh", process::id()));
+        fs::write(&script_path, "#!/bin/sh\necho '  secret-token  '\n")
+            .expect("failed to write test helper script");
+        let mut perms = fs::metadata(&script_path).unwrap().permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(&script_path, perms).unwrap();
+
+        let store = ExternalProcessStore::new(script_path.to_string_lossy().to_string());
+        let result = store.get("any-alias");
+
+        fs::remove_file(&script_path).ok();
+
+        assert_eq!(result.expect("helper should succeed"), "secret-token");
+    }
+}
@@ -45,6 +45,10 @@ pub fn apply_rename_metadata(cfg: &mut Config, old: &str, new: &str) {
         cfg.fingerprints.insert(new.to_string(), fp);
     }
 
+    if let Some(host) = cfg.hosts.remove(old) {
+        cfg.hosts.insert(new.to_string(), host);
+    }
+
     if cfg.last_used_alias.as_deref() == Some(old) {
         cfg.last_used_alias = Some(new.to_string());
     }
@@ -53,6 +57,7 @@ pub fn apply_rename_metadata(cfg: &mut Config, old: &str, new: &str) {
 pub fn apply_delete_metadata(cfg: &mut Config, alias: &str) {
     cfg.aliases.retain(|a| a != alias);
     cfg.fingerprints.remove(alias);
+    cfg.hosts.remove(alias);
     if cfg.last_used_alias.as_deref() == Some(alias) {
         cfg.last_used_alias = None;
     }
@@ -80,7 +85,11 @@ mod tests {
             aliases: vec!["work".into()],
             fingerprints: Default::default(),
             notifications: NotificationConfig::default(),
+            credential: Default::default(),
+            hosts: Default::default(),
+            rules: Default::default(),
             last_used_alias: None,
+            last_notification: None,
         };
         cfg.fingerprints
             .insert("work".into(), token_fingerprint("secret-token"));
@@ -127,7 +136,11 @@ mod tests {
             .into_iter()
             .collect(),
             notifications: NotificationConfig::default(),
+            credential: Default::default(),
+            hosts: Default::default(),
+            rules: Default::default(),
             last_used_alias: Some("work".into()),
+            last_notification: None,
         };
 
         apply_rename_metadata(&mut cfg, "work", "company");
@@ -152,7 +165,11 @@ mod tests {
             .into_iter()
             .collect(),
             notifications: NotificationConfig::default(),
+            credential: Default::default(),
+            hosts: Default::default(),
+            rules: Default::default(),
             last_used_alias: Some("personal".into()),
+            last_notification: None,
         };
 
         apply_delete_metadata(&mut cfg, "personal");
@@ -1,6 +1,8 @@
 mod config;
+mod credential;
 mod logic;
 mod notify;
+mod rules;
 
 use std::{
     io::{self, IsTerminal, Read, Write},
@@ -9,16 +11,17 @@ use std::{
 
 use anyhow::{Context, Result, anyhow, bail};
 use clap::{Parser, Subcommand};
-use keyring::Entry;
 
-use crate::config::{APP_NAME, Config, ensure_alias, load_config, save_config};
+use crate::config::{
+    APP_NAME, Config, DEFAULT_HOST, ensure_alias, host_for, load_config, save_config,
+};
+use crate::credential::credential_store;
 use crate::logic::{
     alias_for_token, apply_delete_metadata, apply_rename_metadata, choose_next_alias,
     token_fingerprint,
 };
-use crate::notify::maybe_notify;
-
-const SERVICE: &str = "github-cli-login-switcher";
+use crate::notify::{NotificationEvent, UserNotification, maybe_notify};
+use crate::rules::{detect_context, matching_alias};
 
 #[derive(Parser, Debug)]
 #[command(name = APP_NAME, version, about = "Switch GitHub auth tokens by profile")]
@@ -30,7 +33,12 @@ struct Cli {
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Store/update token for a profile alias
-    Set { alias: String },
+    Set {
+        alias: String,
+        /// GitHub hostname this alias targets (for GitHub Enterprise Server)
+        #[arg(long)]
+        host: Option<String>,
+    },
     /// Switch to a profile alias, or cycle when omitted
     Use { alias: Option<String> },
     /// Show current active managed alias
@@ -41,6 +49,8 @@ enum Commands {
     Rename { old: String, new: String },
     /// Delete an alias from keychain and config
     Delete { alias: String },
+    /// Clear the active gh session, optionally erasing a stored credential too
+    Logout { alias: Option<String> },
 }
 
 fn main() {
@@ -55,7 +65,7 @@ fn run() -> Result<()> {
     let mut config = load_config()?;
 
     match cli.command {
-        Some(Commands::Set { alias }) => set_token(&alias, &mut config),
+        Some(Commands::Set { alias, host }) => set_token(&alias, host.as_deref(), &mut config),
         Some(Commands::Use { alias }) => use_token(alias, &mut config),
         Some(Commands::Current) => current_alias(&config).map(|alias| {
             println!("{}", alias.unwrap_or_else(|| "unknown".to_string()));
@@ -68,25 +78,23 @@ fn run() -> Result<()> {
         }
         Some(Commands::Rename { old, new }) => rename_alias(&old, &new, &mut config),
         Some(Commands::Delete { alias }) => delete_alias(&alias, &mut config),
+        Some(Commands::Logout { alias }) => logout(alias.as_deref(), &mut config),
         None => use_token(None, &mut config),
     }
 }
 
-fn entry(alias: &str) -> Result<Entry> {
-    Entry::new(SERVICE, alias).context("failed to create keyring entry")
-}
-
-fn set_token(alias: &str, cfg: &mut Config) -> Result<()> {
+fn set_token(alias: &str, host: Option<&str>, cfg: &mut Config) -> Result<()> {
     let token = read_token_from_user()?;
     if token.trim().is_empty() {
         bail!("token is empty");
     }
 
-    entry(alias)?
-        .set_password(token.trim())
-        .with_context(|| format!("failed storing token for alias '{alias}'"))?;
+    credential_store(cfg).store(alias, token.trim())?;
 
     ensure_alias(cfg, alias);
+    if let Some(host) = host {
+        cfg.hosts.insert(alias.to_string(), host.to_string());
+    }
     cfg.fingerprints
         .insert(alias.to_string(), token_fingerprint(token.trim()));
     save_config(cfg)?;
@@ -102,11 +110,15 @@ fn use_token(alias_arg: Option<String>, cfg: &mut Config) -> Result<()> {
         None => pick_next_alias(cfg)?,
     };
 
-    let token = entry(&target)?
-        .get_password()
-        .with_context(|| format!("no token found for alias '{target}'"))?;
+    if current_alias(cfg)?.as_deref() == Some(target.as_str()) {
+        println!("{target}");
+        return Ok(());
+    }
+
+    let token = credential_store(cfg).get(&target)?;
+    let host = host_for(cfg, &target).to_string();
 
-    let switched = switch_gh_token(&token);
+    let switched = switch_gh_token(&token, &host);
 
     match switched {
         Ok(()) => {
@@ -114,15 +126,19 @@ fn use_token(alias_arg: Option<String>, cfg: &mut Config) -> Result<()> {
             cfg.fingerprints
                 .insert(target.clone(), token_fingerprint(&token));
             cfg.last_used_alias = Some(target.clone());
-            save_config(cfg)?;
 
             maybe_notify(
                 cfg,
                 implicit_cycle,
-                "GitHub token switched",
-                &format!("Switched GitHub token: {target}"),
+                UserNotification {
+                    event: NotificationEvent::Switched,
+                    alias: &target,
+                    title: "GitHub token switched",
+                    body: &format!("Switched GitHub token: {target}"),
+                },
             );
 
+            save_config(cfg)?;
             println!("{target}");
             Ok(())
         }
@@ -130,20 +146,33 @@ fn use_token(alias_arg: Option<String>, cfg: &mut Config) -> Result<()> {
             maybe_notify(
                 cfg,
                 implicit_cycle,
-                "GitHub token switch failed",
-                &format!("Failed switching to: {target}"),
+                UserNotification {
+                    event: NotificationEvent::Failed,
+                    alias: &target,
+                    title: "GitHub token switch failed",
+                    body: &format!("Failed switching to: {target}"),
+                },
             );
+            let _ = save_config(cfg);
             Err(err)
         }
     }
 }
 
 fn current_alias(cfg: &Config) -> Result<Option<String>> {
-    let Some(active_token) = gh_current_token().ok() else {
-        return Ok(None);
-    };
+    let mut hosts: Vec<&str> = cfg.aliases.iter().map(|a| host_for(cfg, a)).collect();
+    hosts.sort_unstable();
+    hosts.dedup();
+
+    for host in hosts {
+        if let Ok(token) = gh_current_token(host) {
+            if let Some(alias) = alias_for_token(cfg, &token) {
+                return Ok(Some(alias));
+            }
+        }
+    }
 
-    Ok(alias_for_token(cfg, &active_token))
+    Ok(None)
 }
 
 fn rename_alias(old: &str, new: &str, cfg: &mut Config) -> Result<()> {
@@ -155,17 +184,12 @@ fn rename_alias(old: &str, new: &str, cfg: &mut Config) -> Result<()> {
         bail!("alias '{new}' already exists");
     }
 
-    let token = entry(old)?
-        .get_password()
-        .with_context(|| format!("no token found for alias '{old}'"))?;
-
-    entry(new)?
-        .set_password(&token)
-        .with_context(|| format!("failed storing token for alias '{new}'"))?;
-
-    entry(old)?
-        .delete_credential()
-        .with_context(|| format!("failed deleting old alias '{old}'"))?;
+    let store = credential_store(cfg);
+    let token = store.get(old)?;
+    store.store(new, &token)?;
+    // Strict: the new credential is already written, so a failure erasing
+    // the old one here should be surfaced, not swallowed.
+    store.erase_strict(old)?;
 
     apply_rename_metadata(cfg, old, new);
     save_config(cfg)?;
@@ -174,21 +198,58 @@ fn rename_alias(old: &str, new: &str, cfg: &mut Config) -> Result<()> {
 }
 
 fn delete_alias(alias: &str, cfg: &mut Config) -> Result<()> {
-    let _ = entry(alias)?.delete_credential();
+    credential_store(cfg).erase(alias)?;
     apply_delete_metadata(cfg, alias);
     save_config(cfg)?;
     println!("deleted '{alias}'");
     Ok(())
 }
 
+fn logout(alias: Option<&str>, cfg: &mut Config) -> Result<()> {
+    let host = match alias {
+        Some(alias) => host_for(cfg, alias).to_string(),
+        // Resolve the host of whichever alias is actually active, so
+        // logging out with no argument clears a GHE session too.
+        None => current_alias(cfg)?
+            .map(|active| host_for(cfg, &active).to_string())
+            .unwrap_or_else(|| DEFAULT_HOST.to_string()),
+    };
+    let status = Command::new("gh")
+        .args(["auth", "logout", "--hostname", &host])
+        .status()
+        .context("failed to run 'gh auth logout' (is gh installed?)")?;
+
+    if !status.success() {
+        bail!("'gh auth logout' failed");
+    }
+    // Persist the logout before attempting to erase a stored credential, so
+    // a failing erase can't discard the fact that 'gh auth logout' already
+    // succeeded.
+    cfg.last_used_alias = None;
+    save_config(cfg)?;
+
+    if let Some(alias) = alias {
+        credential_store(cfg).erase(alias)?;
+        println!("logged out and erased credential for '{alias}'");
+    } else {
+        println!("logged out");
+    }
+
+    Ok(())
+}
+
 fn pick_next_alias(cfg: &Config) -> Result<String> {
+    if let Some(alias) = matching_alias(&cfg.rules, &detect_context()) {
+        return Ok(alias);
+    }
+
     let current = current_alias(cfg)?;
     choose_next_alias(&cfg.aliases, current.as_deref())
 }
 
-fn gh_current_token() -> Result<String> {
+fn gh_current_token(host: &str) -> Result<String> {
     let output = Command::new("gh")
-        .args(["auth", "token"])
+        .args(["auth", "token", "--hostname", host])
         .output()
         .context("failed to run 'gh auth token' (is gh installed?)")?;
 
@@ -199,9 +260,9 @@ fn gh_current_token() -> Result<String> {
     String::from_utf8(output.stdout).context("gh output was not utf-8")
 }
 
-fn switch_gh_token(token: &str) -> Result<()> {
+fn switch_gh_token(token: &str, host: &str) -> Result<()> {
     let mut child = Command::new("gh")
-        .args(["auth", "login", "--hostname", "github.com", "--with-token"])
+        .args(["auth", "login", "--hostname", host, "--with-token"])
         .stdin(Stdio::piped())
         .stdout(Stdio::inherit())
         .stderr(Stdio::inherit())
@@ -1,15 +1,91 @@
-use std::{io, io::IsTerminal, process::Command};
+use std::{
+    io, io::IsTerminal,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use anyhow::{Context, Result, bail};
 
-use crate::config::Config;
+use crate::config::{
+    APP_NAME, Config, EmailNotificationConfig, LastNotification, WebhookNotificationConfig,
+};
 
-pub fn maybe_notify(cfg: &Config, implicit_cycle: bool, title: &str, body: &str) {
+/// Which event a notification is reporting, mirrored in the webhook
+/// payload's `event` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationEvent {
+    Switched,
+    Failed,
+}
+
+impl NotificationEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            NotificationEvent::Switched => "switched",
+            NotificationEvent::Failed => "failed",
+        }
+    }
+}
+
+/// A single notification to deliver to whichever channels are enabled.
+pub struct UserNotification<'a> {
+    pub event: NotificationEvent,
+    pub alias: &'a str,
+    pub title: &'a str,
+    pub body: &'a str,
+}
+
+pub fn maybe_notify(cfg: &mut Config, implicit_cycle: bool, notification: UserNotification) {
     if !should_notify(cfg, implicit_cycle) {
         return;
     }
 
-    let _ = send_notification(title, body);
+    if is_duplicate(cfg, &notification) {
+        return;
+    }
+
+    if let Err(err) = send_desktop(notification.title, notification.body) {
+        eprintln!("warning: desktop notification failed: {err:#}");
+    }
+
+    if let Some(email) = &cfg.notifications.email {
+        if let Err(err) = send_email(email, &notification) {
+            eprintln!("warning: email notification failed: {err:#}");
+        }
+    }
+
+    if let Some(webhook) = &cfg.notifications.webhook {
+        if let Err(err) = send_webhook(webhook, &notification) {
+            eprintln!("warning: webhook notification failed: {err:#}");
+        }
+    }
+
+    cfg.last_notification = Some(LastNotification {
+        event: notification.event.as_str().to_string(),
+        alias: notification.alias.to_string(),
+        unix_timestamp: unix_now(),
+    });
+}
+
+/// Checks whether `notification` repeats the last one delivered (same
+/// event and alias) within the configured `dedupe_seconds` window.
+fn is_duplicate(cfg: &Config, notification: &UserNotification) -> bool {
+    let Some(last) = &cfg.last_notification else {
+        return false;
+    };
+
+    if last.event != notification.event.as_str() || last.alias != notification.alias {
+        return false;
+    }
+
+    unix_now().saturating_sub(last.unix_timestamp) < cfg.notifications.dedupe_seconds
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 fn should_notify(cfg: &Config, implicit_cycle: bool) -> bool {
@@ -32,23 +108,38 @@ fn should_notify(cfg: &Config, implicit_cycle: bool) -> bool {
     true
 }
 
-fn send_notification(title: &str, body: &str) -> Result<()> {
+/// Escapes a string for embedding in a double-quoted AppleScript literal.
+fn escape_for_script(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escapes a string for embedding in a single-quoted PowerShell literal.
+/// Single-quoted strings in PowerShell are fully literal — no `$`
+/// interpolation or `` ` `` escape processing — so doubling `'` is the
+/// only escaping required.
+fn escape_for_powershell_literal(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+fn run_notification_script(cmd: &str, args: &[&str]) -> Result<()> {
+    let status = Command::new(cmd)
+        .args(args)
+        .status()
+        .with_context(|| format!("failed to run {cmd}"))?;
+
+    if !status.success() {
+        bail!("{cmd} notification failed");
+    }
+    Ok(())
+}
+
+fn send_desktop(title: &str, body: &str) -> Result<()> {
     #[cfg(target_os = "macos")]
     {
-        let esc_title = title.replace('"', "\\\"");
-        let esc_body = body.replace('"', "\\\"");
+        let esc_title = escape_for_script(title);
+        let esc_body = escape_for_script(body);
         let script = format!("display notification \"{esc_body}\" with title \"{esc_title}\"");
-
-        let status = Command::new("osascript")
-            .arg("-e")
-            .arg(script)
-            .status()
-            .context("failed to run osascript")?;
-
-        if !status.success() {
-            bail!("osascript notification failed");
-        }
-        return Ok(());
+        return run_notification_script("osascript", &["-e", &script]);
     }
 
     #[cfg(target_os = "linux")]
@@ -65,6 +156,139 @@ fn send_notification(title: &str, body: &str) -> Result<()> {
         return Ok(());
     }
 
+    #[cfg(target_os = "windows")]
+    {
+        // Single-quoted so neither `$` interpolation nor `` ` `` escapes in
+        // the caller-controlled title/body can run arbitrary PowerShell.
+        let esc_title = escape_for_powershell_literal(title);
+        let esc_body = escape_for_powershell_literal(body);
+        let script = format!(
+            "[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] > $null; \
+             $template = [Windows.UI.Notifications.ToastNotificationManager]::GetTemplateContent([Windows.UI.Notifications.ToastTemplateType]::ToastText02); \
+             $texts = $template.GetElementsByTagName('text'); \
+             $texts.Item(0).AppendChild($template.CreateTextNode('{esc_title}')) | Out-Null; \
+             $texts.Item(1).AppendChild($template.CreateTextNode('{esc_body}')) | Out-Null; \
+             $toast = [Windows.UI.Notifications.ToastNotification]::new($template); \
+             [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('{APP_NAME}').Show($toast)"
+        );
+        return run_notification_script("powershell", &["-NoProfile", "-Command", &script]);
+    }
+
     #[allow(unreachable_code)]
     Ok(())
 }
+
+fn send_email(cfg: &EmailNotificationConfig, notification: &UserNotification) -> Result<()> {
+    use lettre::{
+        Message, SmtpTransport, Transport,
+        transport::smtp::authentication::Credentials,
+    };
+
+    use crate::config::SmtpEncryption;
+
+    let email = Message::builder()
+        .from(cfg.from.parse().context("invalid 'from' address")?)
+        .to(cfg.to.parse().context("invalid 'to' address")?)
+        .subject(notification.title)
+        .body(notification.body.to_string())
+        .context("failed to build notification email")?;
+
+    let creds = Credentials::new(cfg.username.clone(), cfg.password.clone());
+    let builder = match cfg.encryption {
+        // Implicit TLS from the first byte, used on port 465.
+        SmtpEncryption::Wrapper => SmtpTransport::relay(&cfg.smtp_host),
+        // Plaintext greeting, then upgrade — what port 587 expects.
+        SmtpEncryption::StartTls => SmtpTransport::starttls_relay(&cfg.smtp_host),
+        // No encryption at all; only sensible against localhost/test relays.
+        SmtpEncryption::None => Ok(SmtpTransport::builder_dangerous(&cfg.smtp_host)),
+    }
+    .context("failed to configure SMTP relay")?;
+
+    let mailer = builder.port(cfg.smtp_port).credentials(creds).build();
+
+    mailer
+        .send(&email)
+        .context("failed to send notification email")?;
+    Ok(())
+}
+
+fn send_webhook(cfg: &WebhookNotificationConfig, notification: &UserNotification) -> Result<()> {
+    let payload = ureq::json!({
+        "event": notification.event.as_str(),
+        "alias": notification.alias,
+        "title": notification.title,
+        "body": notification.body,
+    });
+
+    let mut request = ureq::post(&cfg.url);
+    for (name, value) in &cfg.headers {
+        request = request.set(name, value);
+    }
+
+    request
+        .send_json(payload)
+        .context("failed to POST notification webhook")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::{Config, LastNotification};
+
+    use super::{NotificationEvent, UserNotification, is_duplicate, unix_now};
+
+    fn notification(event: NotificationEvent, alias: &str) -> UserNotification<'_> {
+        UserNotification {
+            event,
+            alias,
+            title: "title",
+            body: "body",
+        }
+    }
+
+    #[test]
+    fn is_duplicate_false_with_no_prior_notification() {
+        let cfg = Config::default();
+        assert!(!is_duplicate(&cfg, &notification(NotificationEvent::Switched, "work")));
+    }
+
+    #[test]
+    fn is_duplicate_true_for_same_event_and_alias_within_window() {
+        let mut cfg = Config::default();
+        cfg.notifications.dedupe_seconds = 5;
+        cfg.last_notification = Some(LastNotification {
+            event: NotificationEvent::Switched.as_str().to_string(),
+            alias: "work".to_string(),
+            unix_timestamp: unix_now(),
+        });
+
+        assert!(is_duplicate(&cfg, &notification(NotificationEvent::Switched, "work")));
+    }
+
+    #[test]
+    fn is_duplicate_false_once_window_has_elapsed() {
+        let mut cfg = Config::default();
+        cfg.notifications.dedupe_seconds = 5;
+        cfg.last_notification = Some(LastNotification {
+            event: NotificationEvent::Switched.as_str().to_string(),
+            alias: "work".to_string(),
+            unix_timestamp: unix_now().saturating_sub(10),
+        });
+
+        assert!(!is_duplicate(&cfg, &notification(NotificationEvent::Switched, "work")));
+    }
+
+    #[test]
+    fn is_duplicate_false_for_different_alias_or_event() {
+        let mut cfg = Config::default();
+        cfg.notifications.dedupe_seconds = 5;
+        cfg.last_notification = Some(LastNotification {
+            event: NotificationEvent::Switched.as_str().to_string(),
+            alias: "work".to_string(),
+            unix_timestamp: unix_now(),
+        });
+
+        assert!(!is_duplicate(&cfg, &notification(NotificationEvent::Switched, "personal")));
+        assert!(!is_duplicate(&cfg, &notification(NotificationEvent::Failed, "work")));
+    }
+}
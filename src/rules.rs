@@ -0,0 +1,161 @@
+use std::{env, path::PathBuf, process::Command};
+
+use crate::config::Rule;
+
+/// Context a rule is matched against: the current working directory and,
+/// when inside a git repo, its `origin` remote URL.
+pub struct MatchContext {
+    pub cwd: PathBuf,
+    pub origin_remote: Option<String>,
+}
+
+/// Detects the current directory and git `origin` remote, for matching
+/// against `[[rules]]` in the config.
+pub fn detect_context() -> MatchContext {
+    MatchContext {
+        cwd: env::current_dir().unwrap_or_default(),
+        origin_remote: git_origin_remote(),
+    }
+}
+
+fn git_origin_remote() -> Option<String> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8(output.stdout)
+        .ok()
+        .map(|url| url.trim().to_string())
+}
+
+/// Returns the alias of the first rule whose matcher matches `ctx`'s
+/// directory or remote URL.
+pub fn matching_alias(rules: &[Rule], ctx: &MatchContext) -> Option<String> {
+    let cwd = ctx.cwd.to_string_lossy();
+
+    rules
+        .iter()
+        .find(|rule| {
+            let pattern = expand_tilde(&rule.matcher);
+            glob_match(&pattern, &cwd)
+                || ctx
+                    .origin_remote
+                    .as_deref()
+                    .is_some_and(|remote| glob_match(&pattern, remote))
+        })
+        .map(|rule| rule.alias.clone())
+}
+
+fn expand_tilde(pattern: &str) -> String {
+    match pattern.strip_prefix("~/").zip(dirs::home_dir()) {
+        Some((rest, home)) => format!("{}/{rest}", home.display()),
+        None => pattern.to_string(),
+    }
+}
+
+/// Matches `text` against `pattern`, where `*` in `pattern` matches any
+/// run of characters (including none).
+fn glob_match(pattern: &str, text: &str) -> bool {
+    fn inner(pattern: &[u8], text: &[u8]) -> bool {
+        match (pattern.first(), text.first()) {
+            (None, None) => true,
+            (Some(b'*'), _) => inner(&pattern[1..], text) || (!text.is_empty() && inner(pattern, &text[1..])),
+            (Some(p), Some(t)) if p == t => inner(&pattern[1..], &text[1..]),
+            _ => false,
+        }
+    }
+
+    inner(pattern.as_bytes(), text.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::config::Rule;
+
+    use super::{MatchContext, glob_match, matching_alias};
+
+    fn rule(matcher: &str, alias: &str) -> Rule {
+        Rule {
+            matcher: matcher.to_string(),
+            alias: alias.to_string(),
+        }
+    }
+
+    #[test]
+    fn glob_match_handles_prefix_and_substring_patterns() {
+        assert!(glob_match("/home/user/work/**", "/home/user/work/acme/repo"));
+        assert!(!glob_match("/home/user/work/**", "/home/user/personal/repo"));
+        assert!(glob_match("*github.acme.com*", "git@github.acme.com:acme/repo.git"));
+        assert!(glob_match("*/acme-org/*", "https://github.com/acme-org/repo.git"));
+        assert!(!glob_match("*/acme-org/*", "https://github.com/other-org/repo.git"));
+    }
+
+    #[test]
+    fn matching_alias_matches_by_directory() {
+        let rules = vec![rule("/home/user/work/**", "work")];
+        let ctx = MatchContext {
+            cwd: PathBuf::from("/home/user/work/acme/repo"),
+            origin_remote: None,
+        };
+
+        assert_eq!(matching_alias(&rules, &ctx), Some("work".to_string()));
+    }
+
+    #[test]
+    fn matching_alias_matches_by_remote_when_directory_misses() {
+        let rules = vec![rule("*/acme-org/*", "work-ghe")];
+        let ctx = MatchContext {
+            cwd: PathBuf::from("/home/user/elsewhere"),
+            origin_remote: Some("https://github.com/acme-org/repo.git".to_string()),
+        };
+
+        assert_eq!(matching_alias(&rules, &ctx), Some("work-ghe".to_string()));
+    }
+
+    #[test]
+    fn matching_alias_returns_first_match_in_rule_order() {
+        let rules = vec![
+            rule("/home/user/work/**", "work"),
+            rule("*/acme-org/*", "work-ghe"),
+        ];
+        let ctx = MatchContext {
+            cwd: PathBuf::from("/home/user/work/repo"),
+            origin_remote: Some("https://github.com/acme-org/repo.git".to_string()),
+        };
+
+        assert_eq!(matching_alias(&rules, &ctx), Some("work".to_string()));
+    }
+
+    #[test]
+    fn matching_alias_returns_none_when_nothing_matches() {
+        let rules = vec![rule("/home/user/work/**", "work")];
+        let ctx = MatchContext {
+            cwd: PathBuf::from("/home/user/personal/repo"),
+            origin_remote: Some("https://github.com/personal/repo.git".to_string()),
+        };
+
+        assert_eq!(matching_alias(&rules, &ctx), None);
+    }
+
+    #[test]
+    fn matching_alias_expands_leading_tilde_against_home_dir() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+
+        let rules = vec![rule("~/work/**", "work")];
+        let ctx = MatchContext {
+            cwd: home.join("work").join("acme-repo"),
+            origin_remote: None,
+        };
+
+        assert_eq!(matching_alias(&rules, &ctx), Some("work".to_string()));
+    }
+}